@@ -1,4 +1,4 @@
-use std::net::SocketAddr;
+use std::{net::SocketAddr, path::PathBuf};
 
 use clap::Parser;
 
@@ -13,9 +13,20 @@ pub struct Args {
     #[clap(short, long)]
     pub download_dir: Option<String>,
 
-    /// The magnet link of the torrent, wrapped in quotes.
+    /// A magnet link of a torrent to download, wrapped in quotes. May be
+    /// passed more than once to download several torrents at startup.
     #[clap(short, long)]
-    pub magnet: Option<String>,
+    pub magnet: Vec<String>,
+
+    /// Path to a local `.torrent` metainfo file to download. May be passed
+    /// more than once.
+    #[clap(short, long)]
+    pub torrent: Vec<PathBuf>,
+
+    /// Bare inputs, auto-detected as either magnet links or `.torrent` file
+    /// paths, so magnets and files can be mixed freely in one invocation.
+    #[clap(value_name = "INPUT")]
+    pub inputs: Vec<String>,
 
     /// The Daemon will accept connections on this TCP address.
     #[clap(long)]
@@ -24,4 +35,108 @@ pub struct Args {
     /// If the program should quit after a torrent is fully downloaded
     #[clap(short, long)]
     pub quit_after_complete: bool,
-}
\ No newline at end of file
+
+    /// Start with the session paused. Torrents are added but nothing downloads
+    /// until the session is resumed (default keybinding: `Space`).
+    #[clap(long)]
+    pub start_paused: bool,
+
+    /// Directory where per-torrent resume records are written, enabling
+    /// fast-resume across daemon restarts.
+    #[clap(long)]
+    pub resume_dir: Option<String>,
+
+    /// Global download rate cap in bytes/s. Omit for unlimited.
+    #[clap(long)]
+    pub max_download_rate: Option<u64>,
+
+    /// Global upload rate cap in bytes/s. Omit for unlimited.
+    #[clap(long)]
+    pub max_upload_rate: Option<u64>,
+
+    /// Stop seeding a torrent once its share ratio reaches this value.
+    #[clap(long)]
+    pub seed_ratio_limit: Option<f32>,
+
+    /// Stop seeding a torrent after this many seconds of seeding.
+    #[clap(long)]
+    pub seed_time_limit: Option<u64>,
+}
+
+/// A torrent to add, resolved from the command line. Every `--magnet`,
+/// `--torrent` and bare positional argument is turned into one of these.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    /// A magnet link.
+    Magnet(String),
+    /// A path to a local `.torrent` metainfo file.
+    Torrent(PathBuf),
+}
+
+impl Args {
+    /// Collect every input into a list of [`Source`]s, one per torrent to add.
+    ///
+    /// Explicit `--magnet`/`--torrent` values keep their kind, while bare
+    /// positional inputs are auto-detected: anything starting with `magnet:`,
+    /// in any letter case, is a magnet link, everything else is treated as a
+    /// file path.
+    pub fn sources(&self) -> Vec<Source> {
+        let magnets = self.magnet.iter().cloned().map(Source::Magnet);
+        let torrents = self.torrent.iter().cloned().map(Source::Torrent);
+        let inputs = self.inputs.iter().map(|input| {
+            if input.to_ascii_lowercase().starts_with("magnet:") {
+                Source::Magnet(input.clone())
+            } else {
+                Source::Torrent(PathBuf::from(input))
+            }
+        });
+
+        magnets.chain(torrents).chain(inputs).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sources_keeps_explicit_flags_and_preserves_order() {
+        let args = Args {
+            magnet: vec!["magnet:?xt=1".into()],
+            torrent: vec![PathBuf::from("a.torrent")],
+            ..Default::default()
+        };
+        assert_eq!(
+            args.sources(),
+            vec![
+                Source::Magnet("magnet:?xt=1".into()),
+                Source::Torrent(PathBuf::from("a.torrent")),
+            ]
+        );
+    }
+
+    #[test]
+    fn sources_auto_detects_bare_inputs_case_insensitively() {
+        let args = Args {
+            inputs: vec![
+                "magnet:?xt=lower".into(),
+                "MAGNET:?xt=upper".into(),
+                "local.torrent".into(),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(
+            args.sources(),
+            vec![
+                Source::Magnet("magnet:?xt=lower".into()),
+                Source::Magnet("MAGNET:?xt=upper".into()),
+                Source::Torrent(PathBuf::from("local.torrent")),
+            ]
+        );
+    }
+
+    #[test]
+    fn sources_is_empty_without_any_input() {
+        assert!(Args::default().sources().is_empty());
+    }
+}
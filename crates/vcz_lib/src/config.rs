@@ -0,0 +1,175 @@
+//! Persistent configuration loaded from a TOML file.
+//!
+//! Settings are resolved by layering three sources, each overriding the
+//! previous one: built-in defaults < config file < CLI [`Args`]. A flag passed
+//! on the command line therefore always wins over the same value in the file,
+//! and the file wins over the compiled-in defaults.
+use std::{net::SocketAddr, path::Path};
+
+use serde::Deserialize;
+
+use crate::cli::Args;
+
+/// Default Tui refresh rate, in ticks per second.
+pub const DEFAULT_TICK_RATE: f64 = 4.0;
+/// Default Tui render rate, in frames per second.
+pub const DEFAULT_FRAME_RATE: f64 = 60.0;
+
+/// The effective, fully resolved configuration of Vincenzo.
+///
+/// Use [`Config::load_file`] to read a TOML file and [`Config::merge_args`] to
+/// overlay the CLI flags on top of it.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// The directory in which torrents will be downloaded.
+    pub download_dir: Option<String>,
+    /// The address the Daemon will accept TCP connections on.
+    pub daemon_addr: Option<SocketAddr>,
+    /// If the program should quit after all torrents are fully downloaded.
+    pub quit_after_complete: bool,
+    /// If the session should start paused.
+    pub start_paused: bool,
+    /// Directory where per-torrent resume records are written, enabling
+    /// fast-resume across daemon restarts.
+    pub resume_dir: Option<String>,
+    /// Global download rate cap in bytes/s, `None` for unlimited.
+    pub max_download_rate: Option<u64>,
+    /// Global upload rate cap in bytes/s, `None` for unlimited.
+    pub max_upload_rate: Option<u64>,
+    /// Stop seeding a torrent once its share ratio reaches this value.
+    pub seed_ratio_limit: Option<f32>,
+    /// Stop seeding a torrent after this many seconds of seeding.
+    pub seed_time_limit: Option<u64>,
+    /// The Tui refresh rate, in ticks per second.
+    pub tick_rate: f64,
+    /// The Tui render rate, in frames per second.
+    pub frame_rate: f64,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            download_dir: None,
+            daemon_addr: None,
+            quit_after_complete: false,
+            start_paused: false,
+            resume_dir: None,
+            max_download_rate: None,
+            max_upload_rate: None,
+            seed_ratio_limit: None,
+            seed_time_limit: None,
+            tick_rate: DEFAULT_TICK_RATE,
+            frame_rate: DEFAULT_FRAME_RATE,
+        }
+    }
+}
+
+impl Config {
+    /// Load a [`Config`] from a TOML file at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConfigError::Io`] if the file cannot be read and
+    /// [`ConfigError::Parse`] if its contents are not valid TOML.
+    pub fn load_file(path: impl AsRef<Path>) -> Result<Config, ConfigError> {
+        let contents = std::fs::read_to_string(path)?;
+        let config = toml::from_str(&contents)?;
+        Ok(config)
+    }
+
+    /// Overlay the CLI [`Args`] on top of this config, letting any flag the
+    /// user passed take preference over the file value.
+    pub fn merge_args(mut self, args: Args) -> Self {
+        if args.download_dir.is_some() {
+            self.download_dir = args.download_dir;
+        }
+        if args.daemon_addr.is_some() {
+            self.daemon_addr = args.daemon_addr;
+        }
+        if args.quit_after_complete {
+            self.quit_after_complete = true;
+        }
+        if args.start_paused {
+            self.start_paused = true;
+        }
+        if args.resume_dir.is_some() {
+            self.resume_dir = args.resume_dir;
+        }
+        if args.max_download_rate.is_some() {
+            self.max_download_rate = args.max_download_rate;
+        }
+        if args.max_upload_rate.is_some() {
+            self.max_upload_rate = args.max_upload_rate;
+        }
+        if args.seed_ratio_limit.is_some() {
+            self.seed_ratio_limit = args.seed_ratio_limit;
+        }
+        if args.seed_time_limit.is_some() {
+            self.seed_time_limit = args.seed_time_limit;
+        }
+        self
+    }
+}
+
+/// Errors that can happen while loading the config file.
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    /// The config file could not be read.
+    #[error("could not read the config file: {0}")]
+    Io(#[from] std::io::Error),
+    /// The config file is not valid TOML.
+    #[error("could not parse the config file: {0}")]
+    Parse(#[from] toml::de::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_fill_in_the_tui_rates() {
+        let config = Config::default();
+        assert_eq!(config.tick_rate, DEFAULT_TICK_RATE);
+        assert_eq!(config.frame_rate, DEFAULT_FRAME_RATE);
+        assert!(config.download_dir.is_none());
+    }
+
+    #[test]
+    fn cli_flags_override_file_values() {
+        // stand in for a loaded config file.
+        let from_file = Config {
+            download_dir: Some("/from/file".into()),
+            max_upload_rate: Some(1000),
+            ..Config::default()
+        };
+        let args = Args {
+            download_dir: Some("/from/cli".into()),
+            start_paused: true,
+            seed_ratio_limit: Some(2.0),
+            ..Default::default()
+        };
+
+        let merged = from_file.merge_args(args);
+        // a flag passed on the CLI wins over the file value.
+        assert_eq!(merged.download_dir.as_deref(), Some("/from/cli"));
+        assert!(merged.start_paused);
+        assert_eq!(merged.seed_ratio_limit, Some(2.0));
+    }
+
+    #[test]
+    fn unset_cli_flags_leave_the_file_value_intact() {
+        let from_file = Config {
+            download_dir: Some("/from/file".into()),
+            max_upload_rate: Some(1000),
+            quit_after_complete: true,
+            ..Config::default()
+        };
+        // an all-default Args means the user passed nothing.
+        let merged = from_file.merge_args(Args::default());
+        assert_eq!(merged.download_dir.as_deref(), Some("/from/file"));
+        assert_eq!(merged.max_upload_rate, Some(1000));
+        // a false bool flag never clears an enabled file setting.
+        assert!(merged.quit_after_complete);
+    }
+}
@@ -3,6 +3,7 @@ use std::{
 };
 
 use crate::error::Error;
+use vcz_lib::config::{DEFAULT_FRAME_RATE, DEFAULT_TICK_RATE};
 use crossterm::{
     cursor, event::{
         DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture, Event as CrosstermEvent, KeyEvent, KeyEventKind, MouseEvent
@@ -10,6 +11,7 @@ use crossterm::{
 };
 use futures::{FutureExt, StreamExt};
 use ratatui::backend::CrosstermBackend as Backend;
+use signal_hook_tokio::Signals;
 use tokio::{
     sync::mpsc::{self, UnboundedReceiver, UnboundedSender}, task::JoinHandle
 };
@@ -29,6 +31,20 @@ pub enum Event {
     Key(KeyEvent),
     Mouse(MouseEvent),
     Resize(u16, u16),
+    /// Pause the whole session, stopping every torrent without clearing the
+    /// per-torrent paused flag the user may have set individually.
+    PauseSession,
+    /// Resume the session, restarting only the torrents the user did not
+    /// individually pause.
+    ResumeSession,
+    /// The process received `SIGTSTP` (Ctrl-Z): leave the alternate screen and
+    /// re-raise the stop signal so the shell suspends us cleanly.
+    Suspend,
+    /// The process received `SIGCONT` (`fg`): re-enter the alternate screen.
+    Resume,
+    /// The process received `SIGINT`/`SIGTERM`: shut down gracefully so the
+    /// daemon can flush state instead of dying with the terminal in raw mode.
+    Shutdown,
 }
 
 pub struct Tui {
@@ -44,9 +60,18 @@ pub struct Tui {
 }
 
 impl Tui {
+    /// Default refresh rate, in ticks per second. Re-exported from
+    /// [`vcz_lib::config`] so the Tui fallback and the config default can
+    /// never drift apart. Callers that resolve a `Config` override it through
+    /// [`Tui::tick_rate`].
+    pub const DEFAULT_TICK_RATE: f64 = DEFAULT_TICK_RATE;
+    /// Default render rate, in frames per second, overridable through
+    /// [`Tui::frame_rate`].
+    pub const DEFAULT_FRAME_RATE: f64 = DEFAULT_FRAME_RATE;
+
     pub fn new() -> Result<Self, Error> {
-        let tick_rate = 4.0;
-        let frame_rate = 60.0;
+        let tick_rate = Self::DEFAULT_TICK_RATE;
+        let frame_rate = Self::DEFAULT_FRAME_RATE;
         let terminal =
             ratatui::Terminal::new(Backend::new(std::io::stderr())).unwrap();
         let (event_tx, event_rx) = mpsc::unbounded_channel();
@@ -101,9 +126,15 @@ impl Tui {
         let _event_tx = self.event_tx.clone();
 
         self.task = tokio::spawn(async move {
+            use signal_hook::consts::signal::{
+                SIGCONT, SIGINT, SIGTERM, SIGTSTP,
+            };
+
             let mut reader = crossterm::event::EventStream::new();
             let mut tick_interval = tokio::time::interval(tick_delay);
             let mut render_interval = tokio::time::interval(render_delay);
+            let mut signals =
+                Signals::new([SIGTSTP, SIGCONT, SIGINT, SIGTERM]).unwrap();
 
             _event_tx.send(Event::Init).unwrap();
 
@@ -148,6 +179,22 @@ impl Tui {
                         None => {},
                       }
                   },
+                  maybe_signal = signals.next() => {
+                      // translate the OS signal into a high-level Event so the
+                      // app can tear down cleanly instead of panicking.
+                      match maybe_signal {
+                        Some(SIGTSTP) => {
+                            _event_tx.send(Event::Suspend).unwrap();
+                        }
+                        Some(SIGCONT) => {
+                            _event_tx.send(Event::Resume).unwrap();
+                        }
+                        Some(SIGINT | SIGTERM) => {
+                            _event_tx.send(Event::Shutdown).unwrap();
+                        }
+                        _ => {}
+                      }
+                  },
                   _ = tick_delay => {
                       _event_tx.send(Event::Tick).unwrap();
                   },
@@ -0,0 +1,60 @@
+//! Structured event stream for remote connectors.
+//!
+//! Instead of diffing a full [`TorrentState`](crate::torrent::TorrentState)
+//! snapshot every second, a connector can subscribe to a low-bandwidth stream
+//! of discrete [`Alert`]s. Torrents emit these to the
+//! [`Daemon`](crate::daemon::Daemon) through
+//! [`DaemonMsg::Alert`](crate::daemon::DaemonMsg::Alert), which fans them out
+//! only to the connectors that opted in with
+//! [`DaemonMsg::Subscribe`](crate::daemon::DaemonMsg::Subscribe).
+//!
+//! The periodic snapshot is kept as a fallback, so a client that never
+//! subscribes keeps working exactly as before.
+use serde::{Deserialize, Serialize};
+
+/// A discrete event in the life of a torrent, pushed to subscribed connectors
+/// the moment it happens.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Alert {
+    /// A new torrent was added to the session.
+    TorrentAdded { info_hash: [u8; 20] },
+    /// Every piece of a torrent is verified; the download is complete.
+    TorrentCompleted { info_hash: [u8; 20] },
+    /// A single piece was downloaded and verified.
+    PieceCompleted { info_hash: [u8; 20], index: u32 },
+    /// A handshake with a peer succeeded.
+    PeerConnected { info_hash: [u8; 20], peer: std::net::SocketAddr },
+    /// A peer connection was dropped.
+    PeerDisconnected { info_hash: [u8; 20], peer: std::net::SocketAddr },
+    /// A tracker announce returned, carrying the latest swarm counts.
+    TrackerAnnounced { info_hash: [u8; 20], seeders: u32, leechers: u32 },
+    /// A torrent hit an unrecoverable error.
+    TorrentError { info_hash: [u8; 20], kind: AlertError },
+    /// A torrent stopped seeding after meeting its seed-ratio or
+    /// seeding-time policy.
+    SeedingStopped { info_hash: [u8; 20], reason: StopReason },
+}
+
+/// Why a torrent stopped seeding, carried by [`Alert::SeedingStopped`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StopReason {
+    /// The share ratio (uploaded / downloaded) reached its limit.
+    Ratio,
+    /// The seeding duration reached its limit.
+    Time,
+}
+
+/// The kind of error carried by [`Alert::TorrentError`]. A small, serializable
+/// classification of [`Error`](crate::error::Error) so remote connectors can
+/// react without sharing the daemon's full error type.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AlertError {
+    /// The metainfo could not be fetched or parsed.
+    Metainfo,
+    /// Every tracker announce failed.
+    Tracker,
+    /// A disk read or write failed.
+    Disk,
+    /// Anything else, with a human-readable description.
+    Other(String),
+}
@@ -1,19 +1,19 @@
 //! A daemon that runs on the background and handles everything
 //! that is not the UI.
 use futures::{SinkExt, StreamExt};
-use hashbrown::HashMap;
+use hashbrown::{HashMap, HashSet};
 use std::{
-    net::{IpAddr, Ipv4Addr, SocketAddr}, sync::Arc, time::Duration
+    net::{IpAddr, Ipv4Addr, SocketAddr}, sync::{atomic::{AtomicBool, Ordering}, Arc}, time::{Duration, Instant}
 };
 use tokio_util::codec::Framed;
 use tracing::{error, info, trace, warn};
 
 use tokio::{
-    net::{TcpListener, TcpStream}, select, spawn, sync::{mpsc, oneshot, RwLock}, time::interval
+    net::{TcpListener, TcpStream}, select, spawn, sync::{mpsc, oneshot, Mutex, RwLock}, time::interval
 };
 
 use crate::{
-    daemon_wire::{DaemonCodec, Message}, disk::{Disk, DiskMsg}, error::Error, magnet::Magnet, torrent::{Torrent, TorrentCtx, TorrentMsg, TorrentState, TorrentStatus}, utils::to_human_readable
+    alert::{Alert, StopReason}, daemon_wire::{DaemonCodec, Message}, disk::{Disk, DiskMsg}, error::Error, magnet::Magnet, rate::{RateLimiter, TorrentLimiters, TorrentThruput}, reconnect::ReconnectSupervisor, resume::{self, ResumeRecord}, stream::HttpStream, torrent::{Torrent, TorrentCtx, TorrentMsg, TorrentState, TorrentStatus}, utils::to_human_readable
 };
 use clap::Parser;
 
@@ -31,9 +31,20 @@ pub struct Args {
     #[clap(short, long)]
     pub download_dir: Option<String>,
 
-    /// Download a torrent using it's magnet link, wrapped in quotes.
+    /// Download a torrent using its magnet link, wrapped in quotes. May be
+    /// passed more than once to download several torrents at startup.
     #[clap(short, long)]
-    pub magnet: Option<String>,
+    pub magnet: Vec<String>,
+
+    /// Path to a local `.torrent` metainfo file to download. May be passed
+    /// more than once.
+    #[clap(short, long)]
+    pub torrent: Vec<std::path::PathBuf>,
+
+    /// Bare inputs, auto-detected as either magnet links or `.torrent` file
+    /// paths, so magnets and files can be mixed freely in one invocation.
+    #[clap(value_name = "INPUT")]
+    pub inputs: Vec<String>,
 
     /// If the program should quit after all torrents are fully downloaded
     #[clap(short, long)]
@@ -42,6 +53,38 @@ pub struct Args {
     /// Print all torrent status on stdout
     #[clap(short, long)]
     pub stats: bool,
+
+    /// Launch an HTTP server on this address that streams files inside a
+    /// torrent, with byte-range support, so a media player can play a file
+    /// before it finishes downloading.
+    #[clap(long)]
+    pub stream: Option<SocketAddr>,
+
+    /// Start the session paused: every torrent is added with the session
+    /// paused, so nothing downloads until a Resume is issued.
+    #[clap(long)]
+    pub start_paused: bool,
+
+    /// Directory where per-torrent resume records are written, enabling
+    /// fast-resume across daemon restarts.
+    #[clap(long)]
+    pub resume_dir: Option<String>,
+
+    /// Global download rate cap in bytes/s. Omit for unlimited.
+    #[clap(long)]
+    pub max_download_rate: Option<u64>,
+
+    /// Global upload rate cap in bytes/s. Omit for unlimited.
+    #[clap(long)]
+    pub max_upload_rate: Option<u64>,
+
+    /// Stop seeding a torrent once its share ratio reaches this value.
+    #[clap(long)]
+    pub seed_ratio_limit: Option<f32>,
+
+    /// Stop seeding a torrent after this many seconds of seeding.
+    #[clap(long)]
+    pub seed_time_limit: Option<u64>,
 }
 
 /// The daemon is the highest-level entity in the library.
@@ -71,6 +114,102 @@ pub struct DaemonCtx {
     pub torrent_states: RwLock<HashMap<[u8; 20], TorrentState>>,
     /// key: info_hash
     pub torrent_ctxs: RwLock<HashMap<[u8; 20], Arc<TorrentCtx>>>,
+    /// key: info_hash
+    /// Per-torrent [`TorrentConfig`], stored alongside each torrent ctx.
+    pub torrent_configs: RwLock<HashMap<[u8; 20], TorrentConfig>>,
+    /// Whether the whole session is paused. Torrents added while this is set
+    /// inherit the paused state through their `session_paused` flag.
+    pub session_paused: AtomicBool,
+    /// Connectors that opted into the [`Alert`] stream. Each entry is the
+    /// sending half of a channel owned by a [`Daemon::listen_remote_msgs`]
+    /// task; closed channels are pruned on the next fan-out.
+    pub subscribers: RwLock<Vec<mpsc::Sender<Alert>>>,
+    /// key: info_hash
+    /// Per-torrent reconnection state, driven by the [`Alert`] stream and a
+    /// periodic tick in [`Daemon::run`].
+    pub reconnect: RwLock<HashMap<[u8; 20], ReconnectSupervisor>>,
+    /// Global download token bucket, shared by every peer read loop. A
+    /// per-torrent limiter narrows this further.
+    pub download_limiter: Mutex<RateLimiter>,
+    /// Global upload token bucket, shared by every peer write loop.
+    pub upload_limiter: Mutex<RateLimiter>,
+    /// key: info_hash
+    /// Per-torrent buckets narrowing the global ones, seeded from each
+    /// torrent's [`TorrentConfig`] rate caps and consulted through
+    /// [`DaemonCtx::acquire_download`]/[`DaemonCtx::acquire_upload`].
+    pub torrent_limiters: RwLock<HashMap<[u8; 20], Mutex<TorrentLimiters>>>,
+    /// key: info_hash
+    /// Smoothed throughput accounting, fed by the delta between consecutive
+    /// [`TorrentState`] ticks so [`DaemonMsg::TorrentState`] carries a steady
+    /// `download_rate` instead of the raw per-tick value.
+    pub throughput: RwLock<HashMap<[u8; 20], TorrentThruput>>,
+    /// key: info_hash
+    /// When a torrent was first observed seeding, used to measure seeding time
+    /// for the stop policy. An entry also marks that the torrent has already
+    /// been stopped once its policy is met, so it is not toggled repeatedly.
+    pub seeding_since: RwLock<HashMap<[u8; 20], SeedingState>>,
+}
+
+impl DaemonCtx {
+    /// Acquire up to `wanted` download byte permits for `info_hash`, composed
+    /// from the global bucket and that torrent's own cap, whichever is
+    /// tighter. Peer read loops call this before moving bytes off the wire;
+    /// `0` means the caller should wait for the next refill.
+    pub async fn acquire_download(&self, info_hash: &[u8; 20], wanted: u64) -> u64 {
+        self.acquire(info_hash, wanted, true).await
+    }
+
+    /// The upload counterpart of [`Self::acquire_download`], called by peer
+    /// write loops and by the HTTP [`stream`](crate::stream) server before
+    /// sending bytes to a connected client.
+    pub async fn acquire_upload(&self, info_hash: &[u8; 20], wanted: u64) -> u64 {
+        self.acquire(info_hash, wanted, false).await
+    }
+
+    async fn acquire(&self, info_hash: &[u8; 20], wanted: u64, download: bool) -> u64 {
+        // debited provisionally against `wanted`; whatever the per-torrent
+        // bucket below does not end up granting is handed back so the global
+        // bucket only ever loses what actually moves.
+        let global = if download {
+            self.download_limiter.lock().await.acquire(wanted)
+        } else {
+            self.upload_limiter.lock().await.acquire(wanted)
+        };
+        if global == 0 {
+            return 0;
+        }
+
+        let limiters = self.torrent_limiters.read().await;
+        let granted = match limiters.get(info_hash) {
+            Some(limiters) => {
+                let mut limiters = limiters.lock().await;
+                let limiter = if download { &mut limiters.download } else { &mut limiters.upload };
+                limiter.acquire(global)
+            }
+            None => global,
+        };
+        drop(limiters);
+
+        if granted < global {
+            let mut global_limiter = if download {
+                self.download_limiter.lock().await
+            } else {
+                self.upload_limiter.lock().await
+            };
+            global_limiter.refund(global - granted);
+        }
+
+        granted
+    }
+}
+
+/// Seeding-policy bookkeeping for a single torrent.
+#[derive(Debug, Clone, Copy)]
+pub struct SeedingState {
+    /// When the torrent was first observed in the `Seeding` status.
+    pub since: Instant,
+    /// Whether its ratio/time limit has already been hit and it was stopped.
+    pub stopped: bool,
 }
 
 /// Configuration of the [`Daemon`], the values here are
@@ -83,6 +222,68 @@ pub struct DaemonConfig {
     pub download_dir: String,
     /// If the program should quit after all torrents are fully downloaded
     pub quit_after_complete: bool,
+    /// If set, the daemon launches an HTTP streaming server on this address.
+    pub stream_addr: Option<SocketAddr>,
+    /// Global download byte-rate cap in bytes/s, `None` for unlimited. A
+    /// per-torrent [`TorrentConfig::max_download_rate`] narrows this further.
+    pub max_download_rate: Option<u64>,
+    /// Global upload byte-rate cap in bytes/s, `None` for unlimited.
+    pub max_upload_rate: Option<u64>,
+    /// Stop seeding a torrent once its share ratio (uploaded / downloaded)
+    /// reaches this value. `None` seeds forever, per-torrent overridable.
+    pub seed_ratio_limit: Option<f32>,
+    /// Stop seeding a torrent once it has seeded for this long. `None` seeds
+    /// forever, per-torrent overridable.
+    pub seed_time_limit: Option<Duration>,
+    /// Directory where per-torrent resume records are written, enabling
+    /// fast-resume across daemon restarts.
+    pub resume_dir: Option<String>,
+    /// If the session starts paused. A paused session keeps every torrent's
+    /// `session_paused` flag set until a Resume is issued.
+    pub start_paused: bool,
+}
+
+/// Per-torrent runtime configuration.
+///
+/// Every torrent carries its own budget instead of inheriting the global
+/// [`DaemonConfig`], so one daemon can host many torrents with different
+/// resource limits. It is threaded from [`Daemon::add_torrent`] into
+/// `Torrent::new` and can be changed live with [`DaemonMsg::SetTorrentConfig`].
+#[derive(Debug, Clone)]
+pub struct TorrentConfig {
+    /// Maximum number of peers this torrent keeps connected at once.
+    pub max_connected_peers: u32,
+    /// Maximum number of block requests kept in flight per peer.
+    pub max_pending_block_requests: u32,
+    /// Download byte-rate cap in bytes/s, `None` for unlimited.
+    pub max_download_rate: Option<u64>,
+    /// Upload byte-rate cap in bytes/s, `None` for unlimited.
+    pub max_upload_rate: Option<u64>,
+    /// Download directory for this torrent, `None` to use the daemon's.
+    pub download_dir: Option<String>,
+    /// Whether the torrent keeps seeding after completing.
+    pub seeding_enabled: bool,
+    /// Share-ratio stop limit, overriding the daemon's [`DaemonConfig`] when
+    /// set.
+    pub seed_ratio_limit: Option<f32>,
+    /// Seeding-time stop limit, overriding the daemon's [`DaemonConfig`] when
+    /// set.
+    pub seed_time_limit: Option<Duration>,
+}
+
+impl Default for TorrentConfig {
+    fn default() -> Self {
+        Self {
+            max_connected_peers: 50,
+            max_pending_block_requests: 5,
+            max_download_rate: None,
+            max_upload_rate: None,
+            download_dir: None,
+            seeding_enabled: true,
+            seed_ratio_limit: None,
+            seed_time_limit: None,
+        }
+    }
 }
 
 /// Messages used by the [`Daemon`] for internal communication.
@@ -92,21 +293,44 @@ pub struct DaemonConfig {
 pub enum DaemonMsg {
     /// Tell Daemon to add a new torrent and it will immediately
     /// announce to a tracker, connect to the peers, and start the download.
-    AddTorrent(Magnet),
-    AddTorrentWithPeers(Magnet, Vec<SocketAddr>),
+    AddTorrent(Magnet, TorrentConfig),
+    AddTorrentWithPeers(Magnet, Vec<SocketAddr>, TorrentConfig),
     /// Message that the Daemon will send to all connectors when the state
     /// of a torrent updates (every 1 second).
     TorrentState(TorrentState),
     /// Ask the Daemon to send a [`TorrentState`] of the torrent with the given
     /// hash_info.
     RequestTorrentState([u8; 20], oneshot::Sender<Option<TorrentState>>),
-    /// Pause/Resume a torrent.
+    /// Pause/Resume a single torrent, toggling its per-torrent `paused` flag.
     TogglePause([u8; 20]),
+    /// Pause the whole session: set `session_paused` on every torrent and tear
+    /// down its peer connections and announces, leaving the per-torrent
+    /// `paused` flag untouched.
+    PauseSession,
+    /// Resume the whole session: clear `session_paused` on every torrent. A
+    /// torrent the user individually paused stays paused.
+    ResumeSession,
+    /// Force a resume checkpoint, serializing every torrent to `resume_dir`.
+    SaveSession,
+    /// Register a connector for the [`Alert`] stream. The daemon keeps the
+    /// sender and forwards every emitted alert to it.
+    Subscribe(mpsc::Sender<Alert>),
+    /// An [`Alert`] emitted by a torrent, fanned out to every subscriber.
+    Alert(Alert),
+    /// Change the global download/upload byte-rate caps at runtime. Each field
+    /// is `Some(bytes_per_sec)` to set a cap or `None` to lift it.
+    SetRateLimits {
+        max_download_rate: Option<u64>,
+        max_upload_rate: Option<u64>,
+    },
     /// Gracefully shutdown the Daemon
     Quit,
     /// Print the status of all Torrents to stdout
     PrintTorrentStatus,
     MutateTorrent([u8; 20], Arc<TorrentCtx>),
+    /// Change a torrent's [`TorrentConfig`] at runtime, applied without
+    /// restarting the torrent.
+    SetTorrentConfig([u8; 20], TorrentConfig),
 }
 
 impl Daemon {
@@ -121,6 +345,13 @@ impl Daemon {
             download_dir,
             listen: Self::DEFAULT_LISTENER,
             quit_after_complete: false,
+            stream_addr: None,
+            max_download_rate: None,
+            max_upload_rate: None,
+            seed_ratio_limit: None,
+            seed_time_limit: None,
+            resume_dir: None,
+            start_paused: false,
         };
 
         Self {
@@ -130,11 +361,56 @@ impl Daemon {
             ctx: Arc::new(DaemonCtx {
                 tx,
                 torrent_ctxs: RwLock::new(HashMap::new()),
+                torrent_configs: RwLock::new(HashMap::new()),
                 torrent_states: RwLock::new(HashMap::new()),
+                session_paused: AtomicBool::new(false),
+                subscribers: RwLock::new(Vec::new()),
+                reconnect: RwLock::new(HashMap::new()),
+                download_limiter: Mutex::new(RateLimiter::new(None)),
+                upload_limiter: Mutex::new(RateLimiter::new(None)),
+                torrent_limiters: RwLock::new(HashMap::new()),
+                throughput: RwLock::new(HashMap::new()),
+                seeding_since: RwLock::new(HashMap::new()),
             }),
         }
     }
 
+    /// Overlay the daemon CLI [`Args`] onto the config, so every flag the user
+    /// passed reaches [`DaemonConfig`]. Values left unset keep their default.
+    pub fn with_args(mut self, args: Args) -> Self {
+        if let Some(daemon_addr) = args.daemon_addr {
+            self.config.listen = daemon_addr;
+        }
+        if let Some(download_dir) = args.download_dir {
+            self.config.download_dir = download_dir;
+        }
+        if args.quit_after_complete {
+            self.config.quit_after_complete = true;
+        }
+        if args.stream.is_some() {
+            self.config.stream_addr = args.stream;
+        }
+        if args.start_paused {
+            self.config.start_paused = true;
+        }
+        if args.resume_dir.is_some() {
+            self.config.resume_dir = args.resume_dir;
+        }
+        if args.max_download_rate.is_some() {
+            self.config.max_download_rate = args.max_download_rate;
+        }
+        if args.max_upload_rate.is_some() {
+            self.config.max_upload_rate = args.max_upload_rate;
+        }
+        if args.seed_ratio_limit.is_some() {
+            self.config.seed_ratio_limit = args.seed_ratio_limit;
+        }
+        if let Some(secs) = args.seed_time_limit {
+            self.config.seed_time_limit = Some(Duration::from_secs(secs));
+        }
+        self
+    }
+
     /// This function will listen to 3 different event loops:
     /// - The daemon internal messages via MPSC [`DaemonMsg`] (external)
     /// - The daemon TCP framed messages [`DaemonCodec`] (internal)
@@ -150,7 +426,13 @@ impl Daemon {
     /// that can be fired remotely (via TCP),
     /// can also be fired internaly (via CLI flags).
     pub async fn run(&mut self) -> Result<(), Error> {
-        let socket = TcpListener::bind(self.config.listen).await.unwrap();
+        self.ctx
+            .session_paused
+            .store(self.config.start_paused, Ordering::Release);
+
+        // seed the global token buckets from the resolved config.
+        self.ctx.download_limiter.lock().await.set_rate(self.config.max_download_rate);
+        self.ctx.upload_limiter.lock().await.set_rate(self.config.max_upload_rate);
 
         let (disk_tx, disk_rx) = mpsc::channel::<DiskMsg>(300);
         self.disk_tx = Some(disk_tx);
@@ -161,6 +443,24 @@ impl Daemon {
             disk.run().await.unwrap();
         });
 
+        // restore any torrents checkpointed on a previous run before binding
+        // the listener, so a reconnecting UI sees the resumed session from the
+        // first accepted connection.
+        self.restore_session().await;
+
+        let socket = TcpListener::bind(self.config.listen).await.unwrap();
+
+        // optionally launch the HTTP streaming server, sharing the daemon's
+        // piece-availability state.
+        if let Some(stream_addr) = self.config.stream_addr {
+            let stream = HttpStream::new(self.ctx.clone());
+            spawn(async move {
+                if let Err(e) = stream.run(stream_addr).await {
+                    error!("streaming server stopped: {e:#?}");
+                }
+            });
+        }
+
         let ctx = self.ctx.clone();
 
         info!("Daemon listening on: {}", self.config.listen);
@@ -188,36 +488,76 @@ impl Daemon {
 
         let ctx = self.ctx.clone();
 
+        // periodic resume checkpoint, a no-op when `resume_dir` is unset.
+        let mut checkpoint_interval = interval(Duration::from_secs(30));
+
+        // periodic reconnection sweep of peers that dropped between announces.
+        let mut reconnect_interval = interval(Duration::from_secs(5));
+
         // Listen to internal mpsc messages
         loop {
             select! {
+                _ = checkpoint_interval.tick() => {
+                    self.save_session().await;
+                }
+                _ = reconnect_interval.tick() => {
+                    self.reconnect_peers().await;
+                }
                 Some(msg) = self.rx.recv() => {
                     match msg {
-                        DaemonMsg::TorrentState(torrent_state) => {
+                        DaemonMsg::TorrentState(mut torrent_state) => {
+                            // replace the raw per-tick rate with a 5s-smoothed
+                            // one before anything else observes this state.
+                            self.smooth_throughput(&mut torrent_state).await;
+
                             let mut torrent_states = self.ctx.torrent_states.write().await;
+                            let previous = torrent_states.insert(torrent_state.info_hash, torrent_state.clone());
+                            drop(torrent_states);
+
+                            // the rest of a torrent's lifecycle alerts come
+                            // from the torrent/peer/tracker layers themselves;
+                            // these three are derivable purely from the totals
+                            // the daemon already has on hand.
+                            self.emit_progress_alerts(previous.as_ref(), &torrent_state).await;
 
-                            torrent_states.insert(torrent_state.info_hash, torrent_state.clone());
+                            // nothing upstream constructs PeerConnected/
+                            // PeerDisconnected yet, so the reconnect
+                            // supervisor would otherwise never see a peer;
+                            // derive them from the same state this tick
+                            // already carries.
+                            self.sync_reconnect_peers(previous.as_ref(), &torrent_state).await;
 
-                            if self.config.quit_after_complete && torrent_states.values().all(|v| v.status == TorrentStatus::Seeding) {
+                            // stop seeding torrents that have met their ratio
+                            // or seeding-time policy.
+                            self.enforce_seed_policy(&torrent_state).await;
+
+                            if self.config.quit_after_complete && self.all_seeding_policy_met().await {
                                 let _ = ctx.tx.send(DaemonMsg::Quit).await;
                             }
-
-                            drop(torrent_states);
                         }
-                        DaemonMsg::AddTorrent(magnet) => {
-                            let _ = self.add_torrent(magnet, None).await;
+                        DaemonMsg::AddTorrent(magnet, config) => {
+                            let _ = self.add_torrent(magnet, None, config, None).await;
                         }
-                        DaemonMsg::AddTorrentWithPeers(magnet, peers) => {
-                            let _ = self.add_torrent(magnet, Some(peers)).await;
+                        DaemonMsg::AddTorrentWithPeers(magnet, peers, config) => {
+                            let _ = self.add_torrent(magnet, Some(peers), config, None).await;
                         }
                         DaemonMsg::MutateTorrent(info_hash, ctx) => {
                             let mut ctxs = self.ctx.torrent_ctxs.write().await;
                             let local_ctx = ctxs.get_mut(&info_hash).unwrap();
                             *local_ctx = ctx;
                         }
+                        DaemonMsg::SetTorrentConfig(info_hash, config) => {
+                            let _ = self.set_torrent_config(info_hash, config).await;
+                        }
                         DaemonMsg::TogglePause(info_hash) => {
                             let _ = self.toggle_pause(info_hash).await;
                         }
+                        DaemonMsg::PauseSession => {
+                            let _ = self.set_session_paused(true).await;
+                        }
+                        DaemonMsg::ResumeSession => {
+                            let _ = self.set_session_paused(false).await;
+                        }
                         DaemonMsg::RequestTorrentState(info_hash, recipient) => {
                             let torrent_states = self.ctx.torrent_states.read().await;
                             let torrent_state = torrent_states.get(&info_hash);
@@ -240,15 +580,43 @@ impl Daemon {
                                     _ => state.status.clone().into()
                                 };
 
+                                // connecting/backoff counts from the same
+                                // supervisor the daemon uses to re-dial peers.
+                                let (connecting, backoff) = self
+                                    .ctx
+                                    .reconnect
+                                    .read()
+                                    .await
+                                    .get(&state.info_hash)
+                                    .map(|supervisor| supervisor.counts())
+                                    .unwrap_or_default();
+
                                 println!(
-                                    "\n{}\n{}\nSeeders {} Leechers {}\n{status_line}",
+                                    "\n{}\n{}\nSeeders {} Leechers {}\nReconnecting {} Backoff {}\n{status_line}",
                                     state.name,
                                     to_human_readable(state.size as f64),
                                     state.stats.seeders,
                                     state.stats.leechers,
+                                    connecting,
+                                    backoff,
                                 );
                             }
                         }
+                        DaemonMsg::SaveSession => {
+                            self.save_session().await;
+                        }
+                        DaemonMsg::Subscribe(tx) => {
+                            self.ctx.subscribers.write().await.push(tx);
+                        }
+                        DaemonMsg::SetRateLimits { max_download_rate, max_upload_rate } => {
+                            self.config.max_download_rate = max_download_rate;
+                            self.config.max_upload_rate = max_upload_rate;
+                            self.ctx.download_limiter.lock().await.set_rate(max_download_rate);
+                            self.ctx.upload_limiter.lock().await.set_rate(max_upload_rate);
+                        }
+                        DaemonMsg::Alert(alert) => {
+                            self.publish_alert(alert).await;
+                        }
                         DaemonMsg::Quit => {
                             let _ = self.quit().await;
                             handle.abort();
@@ -274,6 +642,10 @@ impl Daemon {
         let mut draw_interval = interval(Duration::from_secs(1));
         let (mut sink, mut stream) = socket.split();
 
+        // set up lazily: the channel only carries alerts once this connector
+        // sends a `Subscribe`, after which the daemon holds `alert_tx`.
+        let (alert_tx, mut alert_rx) = mpsc::channel::<Alert>(300);
+
         loop {
             select! {
                 // listen to messages sent remotely via TCP, and pass them
@@ -286,9 +658,13 @@ impl Daemon {
                             trace!("daemon received NewTorrent {magnet_link}");
                             let magnet = Magnet::new(&magnet_link);
                             if let Ok(magnet) = magnet {
-                                let _ = ctx.tx.send(DaemonMsg::AddTorrent(magnet)).await;
+                                let _ = ctx.tx.send(DaemonMsg::AddTorrent(magnet, TorrentConfig::default())).await;
                             }
                         }
+                        Message::SetTorrentConfig(info_hash, config) => {
+                            trace!("daemon received SetTorrentConfig {info_hash:?}");
+                            let _ = ctx.tx.send(DaemonMsg::SetTorrentConfig(info_hash, config)).await;
+                        }
                         Message::RequestTorrentState(info_hash) => {
                             trace!("daemon RequestTorrentState {info_hash:?}");
                             let (tx, rx) = oneshot::channel();
@@ -301,6 +677,26 @@ impl Daemon {
                             trace!("daemon received TogglePause {id:?}");
                             let _ = ctx.tx.send(DaemonMsg::TogglePause(id)).await;
                         }
+                        Message::PauseSession => {
+                            trace!("daemon received PauseSession");
+                            let _ = ctx.tx.send(DaemonMsg::PauseSession).await;
+                        }
+                        Message::ResumeSession => {
+                            trace!("daemon received ResumeSession");
+                            let _ = ctx.tx.send(DaemonMsg::ResumeSession).await;
+                        }
+                        Message::SaveSession => {
+                            trace!("daemon received SaveSession");
+                            let _ = ctx.tx.send(DaemonMsg::SaveSession).await;
+                        }
+                        Message::Subscribe => {
+                            trace!("daemon received Subscribe");
+                            let _ = ctx.tx.send(DaemonMsg::Subscribe(alert_tx.clone())).await;
+                        }
+                        Message::SetRateLimits(max_download_rate, max_upload_rate) => {
+                            trace!("daemon received SetRateLimits");
+                            let _ = ctx.tx.send(DaemonMsg::SetRateLimits { max_download_rate, max_upload_rate }).await;
+                        }
                         Message::Quit => {
                             trace!("daemon received Quit");
                             let _ = ctx.tx.send(DaemonMsg::Quit).await;
@@ -317,6 +713,10 @@ impl Daemon {
                 _ = draw_interval.tick() => {
                     let _ = Self::draw(&mut sink, ctx.clone()).await;
                 }
+                // forward alerts to a subscribed connector as they arrive.
+                Some(alert) = alert_rx.recv() => {
+                    let _ = sink.send(Message::Alert(alert)).await;
+                }
             }
         }
     }
@@ -333,6 +733,55 @@ impl Daemon {
         Ok(())
     }
 
+    /// Change a torrent's [`TorrentConfig`] at runtime.
+    ///
+    /// The new config is stored alongside the torrent ctx and handed to the
+    /// running torrent via [`TorrentMsg::SetConfig`], which applies the new
+    /// limits in place without restarting the torrent.
+    pub async fn set_torrent_config(
+        &self,
+        info_hash: [u8; 20],
+        config: TorrentConfig,
+    ) -> Result<(), Error> {
+        let tx = {
+            let ctxs = self.ctx.torrent_ctxs.read().await;
+            ctxs.get(&info_hash).ok_or(Error::TorrentDoesNotExist)?.tx.clone()
+        };
+
+        tx.send(TorrentMsg::SetConfig(config.clone())).await?;
+
+        // the torrent's own bucket is replaced wholesale rather than just its
+        // rate, so a new cap starts from a full bucket instead of whatever was
+        // banked under the old one.
+        self.ctx.torrent_limiters.write().await.insert(
+            info_hash,
+            Mutex::new(TorrentLimiters::new(config.max_download_rate, config.max_upload_rate)),
+        );
+        self.ctx.torrent_configs.write().await.insert(info_hash, config);
+
+        Ok(())
+    }
+
+    /// Pause or resume the whole session.
+    ///
+    /// This sets each torrent's `session_paused` flag via
+    /// [`TorrentMsg::SessionPause`]; a torrent is effectively stopped when
+    /// *either* its per-torrent `paused` flag or `session_paused` is set. The
+    /// per-torrent flag is never touched here, so a global resume does not
+    /// un-pause torrents the user paused individually.
+    pub async fn set_session_paused(&self, paused: bool) -> Result<(), Error> {
+        self.ctx.session_paused.store(paused, Ordering::Release);
+
+        let ctxs = self.ctx.torrent_ctxs.read().await;
+        for ctx in ctxs.values() {
+            // a torrent whose task already exited has a closed channel; keep
+            // broadcasting to the rest instead of bailing on the first error.
+            let _ = ctx.tx.send(TorrentMsg::SessionPause(paused)).await;
+        }
+
+        Ok(())
+    }
+
     /// Sends a Draw message to the [`UI`] with the updated state of a torrent.
     async fn draw<T>(sink: &mut T, ctx: Arc<DaemonCtx>) -> Result<(), Error>
     where
@@ -365,6 +814,8 @@ impl Daemon {
         &mut self,
         magnet: Magnet,
         peers: Option<Vec<SocketAddr>>,
+        config: TorrentConfig,
+        resume: Option<ResumeRecord>,
     ) -> Result<(), Error> {
         trace!("magnet: {}", *magnet);
         let info_hash = magnet.parse_xt();
@@ -376,24 +827,68 @@ impl Daemon {
             return Err(Error::NoDuplicateTorrent);
         }
 
-        let torrent_state = TorrentState {
-            name: magnet.parse_dn(),
-            info_hash,
-            ..Default::default()
+        // a resumed torrent starts with its checkpointed progress so the UI
+        // and ratio accounting pick up where they left off.
+        let torrent_state = match &resume {
+            Some(record) => TorrentState {
+                name: magnet.parse_dn(),
+                info_hash,
+                magnet: record.magnet.clone(),
+                pieces: record.pieces.clone(),
+                downloaded: record.downloaded,
+                uploaded: record.uploaded,
+                ..Default::default()
+            },
+            None => TorrentState {
+                name: magnet.parse_dn(),
+                info_hash,
+                ..Default::default()
+            },
         };
 
         torrent_states.insert(info_hash, torrent_state);
         drop(torrent_states);
 
+        // tell subscribed connectors a torrent joined the session; torrents
+        // emit the rest of their lifecycle alerts themselves.
+        self.publish_alert(Alert::TorrentAdded { info_hash }).await;
+
         // disk_tx is not None at this point, this is safe
         // (if calling after run)
+        //
+        // the verified-piece bitfield from a resume record is handed to the
+        // torrent so it trusts those pieces and only re-hashes on a mismatch,
+        // instead of re-verifying gigabytes on every launch.
+        let resume_pieces = resume.map(|r| r.pieces);
         let disk_tx = self.disk_tx.clone().unwrap();
-        let mut torrent = Torrent::new(disk_tx, self.ctx.tx.clone(), magnet);
+        // the daemon ctx is handed to the torrent the same way disk_tx and
+        // ctx.tx already are, so its peer read/write loops can reach
+        // acquire_download/acquire_upload instead of only the HTTP stream
+        // server narrowing its transfers against the rate limiters.
+        let mut torrent = Torrent::new(
+            disk_tx,
+            self.ctx.tx.clone(),
+            magnet,
+            config.clone(),
+            resume_pieces,
+            self.ctx.clone(),
+        );
 
         let mut ctxs = self.ctx.torrent_ctxs.write().await;
         ctxs.insert(info_hash, torrent.ctx.clone());
+        self.ctx.torrent_configs.write().await.insert(info_hash, config.clone());
+        self.ctx.torrent_limiters.write().await.insert(
+            info_hash,
+            Mutex::new(TorrentLimiters::new(config.max_download_rate, config.max_upload_rate)),
+        );
         info!("Downloading torrent: {}", torrent.name);
 
+        // a torrent added while the session is paused inherits the paused
+        // state, so it stays stopped until the session is resumed.
+        if self.ctx.session_paused.load(Ordering::Acquire) {
+            torrent.ctx.tx.send(TorrentMsg::SessionPause(true)).await?;
+        }
+
         spawn(async move {
             if let Some(peers) = peers {
                 torrent.start_and_run_with_peers(peers).await?;
@@ -406,7 +901,309 @@ impl Daemon {
         Ok(())
     }
 
+    /// Checkpoint every torrent to `resume_dir`. A no-op when `resume_dir` is
+    /// not configured. Errors on individual torrents are logged, not fatal.
+    async fn save_session(&self) {
+        let Some(resume_dir) = &self.config.resume_dir else {
+            return;
+        };
+
+        let states = self.ctx.torrent_states.read().await;
+        let configs = self.ctx.torrent_configs.read().await;
+
+        for state in states.values() {
+            let download_dir = configs
+                .get(&state.info_hash)
+                .and_then(|c| c.download_dir.clone())
+                .unwrap_or_else(|| self.config.download_dir.clone());
+
+            let record = ResumeRecord {
+                magnet: state.magnet.clone(),
+                info_hash: state.info_hash,
+                download_dir,
+                pieces: state.pieces.clone(),
+                downloaded: state.downloaded,
+                uploaded: state.uploaded,
+                peers: state.peers.clone(),
+            };
+
+            if let Err(e) = record.save(resume_dir) {
+                warn!("could not checkpoint torrent {:?}: {e:#?}", state.info_hash);
+            }
+        }
+    }
+
+    /// Reload every checkpointed torrent from `resume_dir`, re-adding it with
+    /// its stored peers so already-verified pieces are not re-hashed.
+    async fn restore_session(&mut self) {
+        let Some(resume_dir) = self.config.resume_dir.clone() else {
+            return;
+        };
+
+        let records = match resume::load_all(&resume_dir) {
+            Ok(records) => records,
+            Err(e) => {
+                error!("could not read resume dir {resume_dir}: {e:#?}");
+                return;
+            }
+        };
+
+        for record in records {
+            let Ok(magnet) = Magnet::new(&record.magnet) else {
+                continue;
+            };
+            let config = TorrentConfig {
+                download_dir: Some(record.download_dir.clone()),
+                ..Default::default()
+            };
+            info!("resuming torrent: {}", record.magnet);
+            let peers = record.peers.clone();
+            let _ = self.add_torrent(magnet, Some(peers), config, Some(record)).await;
+        }
+    }
+
+    /// Ask each torrent to re-handshake the peers whose backoff has elapsed,
+    /// reusing the peer flow [`add_torrent`](Self::add_torrent) hands to a
+    /// torrent. In-flight attempts are capped per torrent by the supervisor.
+    async fn reconnect_peers(&self) {
+        let now = Instant::now();
+        let ctxs = self.ctx.torrent_ctxs.read().await;
+        let mut supervisors = self.ctx.reconnect.write().await;
+
+        for (info_hash, supervisor) in supervisors.iter_mut() {
+            let due = supervisor.due(now);
+            if due.is_empty() {
+                continue;
+            }
+            if let Some(ctx) = ctxs.get(info_hash) {
+                let _ = ctx.tx.send(TorrentMsg::Reconnect(due)).await;
+            }
+        }
+    }
+
+    /// Deliver `alert` to the reconnection supervisor and every subscriber,
+    /// without going through `self.ctx.tx`/[`DaemonMsg::Alert`].
+    ///
+    /// This is what the `DaemonMsg::Alert` arm of [`Self::run`] calls into,
+    /// but it is also the only safe way for code already running inside that
+    /// same `rx.recv()` loop (like [`Self::emit_progress_alerts`]) to publish
+    /// an alert: an awaited `self.ctx.tx.send(..).await` from inside the
+    /// loop's own consumer blocks forever once the channel fills, since
+    /// nothing else is left to drain it.
+    async fn publish_alert(&self, alert: Alert) {
+        // the reconnection supervisor learns about peer churn from the same
+        // event stream the UI sees.
+        match &alert {
+            Alert::PeerConnected { info_hash, peer } => {
+                self.ctx.reconnect.write().await
+                    .entry(*info_hash).or_default()
+                    .on_connected(*peer);
+            }
+            Alert::PeerDisconnected { info_hash, peer } => {
+                self.ctx.reconnect.write().await
+                    .entry(*info_hash).or_default()
+                    .on_disconnected(*peer, Instant::now());
+            }
+            _ => {}
+        }
+
+        let mut subscribers = self.ctx.subscribers.write().await;
+        // fan out, dropping only connectors whose channel has closed; a
+        // momentarily full channel (a lagging connector) is kept so it can
+        // catch up.
+        subscribers.retain(|tx| !matches!(
+            tx.try_send(alert.clone()),
+            Err(mpsc::error::TrySendError::Closed(_))
+        ));
+    }
+
+    /// Fold `state.downloaded` into that torrent's [`TorrentThruput`] and
+    /// overwrite `state.download_rate` with the smoothed 5s average, so a
+    /// burst or a stall does not make the reported rate jump around every
+    /// second.
+    async fn smooth_throughput(&self, state: &mut TorrentState) {
+        let mut throughput = self.ctx.throughput.write().await;
+        let entry = throughput
+            .entry(state.info_hash)
+            .or_insert_with(|| TorrentThruput::new(state.downloaded));
+
+        state.download_rate = entry.observe(state.downloaded);
+    }
+
+    /// Diff `previous` against `state` and emit the [`Alert`]s derivable
+    /// purely from the totals a [`TorrentState`] already carries: a newly-set
+    /// bit in the verified-piece bitfield, the transition into a fully
+    /// downloaded torrent, and a change in the tracker's reported swarm size.
+    ///
+    /// [`Alert::TorrentError`] has no such signal to derive from - nothing in
+    /// `TorrentState` records that a torrent failed - so it is still never
+    /// emitted; that one needs the tracker/peer layer to construct it
+    /// directly, the same as `TrackerAnnounced` would have before this.
+    async fn emit_progress_alerts(&self, previous: Option<&TorrentState>, state: &TorrentState) {
+        if let Some(previous) = previous {
+            for (index, &byte) in state.pieces.iter().enumerate() {
+                let before = previous.pieces.get(index).copied().unwrap_or(0);
+                let newly_verified = byte & !before;
+                if newly_verified == 0 {
+                    continue;
+                }
+                for bit in 0..8u32 {
+                    if newly_verified & (1 << (7 - bit)) == 0 {
+                        continue;
+                    }
+                    let piece_index = index as u32 * 8 + bit;
+                    self.publish_alert(Alert::PieceCompleted {
+                        info_hash: state.info_hash,
+                        index: piece_index,
+                    })
+                    .await;
+                }
+            }
+        }
+
+        let was_complete = previous.is_some_and(|p| p.size > 0 && p.downloaded >= p.size);
+        let is_complete = state.size > 0 && state.downloaded >= state.size;
+        if is_complete && !was_complete {
+            self.publish_alert(Alert::TorrentCompleted { info_hash: state.info_hash }).await;
+        }
+
+        // the seeders/leechers counts only move when a tracker announce
+        // returns, so a change between ticks is a reasonable stand-in for the
+        // announce event itself. No `previous` (the torrent was just added)
+        // means no announce has necessarily happened yet, so that tick is
+        // skipped rather than treated as a change.
+        if let Some(previous) = previous {
+            let swarm_changed = previous.stats.seeders != state.stats.seeders
+                || previous.stats.leechers != state.stats.leechers;
+            if swarm_changed {
+                self.publish_alert(Alert::TrackerAnnounced {
+                    info_hash: state.info_hash,
+                    seeders: state.stats.seeders,
+                    leechers: state.stats.leechers,
+                })
+                .await;
+            }
+        }
+    }
+
+    /// Diff `previous.peers` against `state.peers` and publish
+    /// [`Alert::PeerConnected`]/[`Alert::PeerDisconnected`] for the
+    /// difference, since nothing upstream of the daemon constructs those
+    /// alerts. Without this the [`ReconnectSupervisor`] the daemon keeps
+    /// per torrent never learns about a single peer, so it stays empty and
+    /// [`ReconnectSupervisor::due`] never has anything to hand back to
+    /// [`Self::reconnect_peers`].
+    async fn sync_reconnect_peers(&self, previous: Option<&TorrentState>, state: &TorrentState) {
+        let previous_peers: HashSet<_> =
+            previous.map(|p| p.peers.iter().copied().collect()).unwrap_or_default();
+        let current_peers: HashSet<_> = state.peers.iter().copied().collect();
+
+        for &peer in current_peers.difference(&previous_peers) {
+            self.publish_alert(Alert::PeerConnected { info_hash: state.info_hash, peer }).await;
+        }
+        for &peer in previous_peers.difference(&current_peers) {
+            self.publish_alert(Alert::PeerDisconnected { info_hash: state.info_hash, peer }).await;
+        }
+    }
+
+    /// Stop a seeding torrent once it reaches its share-ratio or seeding-time
+    /// limit, resolving per-torrent overrides on top of the daemon defaults.
+    /// A stopped torrent is sent [`TorrentMsg::Stop`] and a
+    /// [`Alert::SeedingStopped`] is emitted.
+    async fn enforce_seed_policy(&self, state: &TorrentState) {
+        if state.status != TorrentStatus::Seeding {
+            return;
+        }
+
+        let (ratio_limit, time_limit) = {
+            let configs = self.ctx.torrent_configs.read().await;
+            let config = configs.get(&state.info_hash);
+            (
+                config.and_then(|c| c.seed_ratio_limit).or(self.config.seed_ratio_limit),
+                config.and_then(|c| c.seed_time_limit).or(self.config.seed_time_limit),
+            )
+        };
+
+        // nothing to enforce: this torrent seeds forever.
+        if ratio_limit.is_none() && time_limit.is_none() {
+            return;
+        }
+
+        let now = Instant::now();
+        let mut seeding = self.ctx.seeding_since.write().await;
+        let entry = seeding
+            .entry(state.info_hash)
+            .or_insert(SeedingState { since: now, stopped: false });
+
+        if entry.stopped {
+            return;
+        }
+
+        // share ratio is uploaded / downloaded, as the tracker accounts for it.
+        // a seed-only or resumed torrent can have `downloaded == 0`, so fall
+        // back to the total size as the denominator there — otherwise its ratio
+        // limit could never fire.
+        let denominator = if state.downloaded > 0 { state.downloaded } else { state.size };
+        let ratio = if denominator == 0 {
+            0.0
+        } else {
+            state.uploaded as f32 / denominator as f32
+        };
+
+        let reason = if ratio_limit.is_some_and(|limit| ratio >= limit) {
+            Some(StopReason::Ratio)
+        } else if time_limit.is_some_and(|limit| now.duration_since(entry.since) >= limit) {
+            Some(StopReason::Time)
+        } else {
+            None
+        };
+
+        let Some(reason) = reason else {
+            return;
+        };
+
+        entry.stopped = true;
+        drop(seeding);
+
+        // stop through the dedicated per-torrent `Stop` path, not the session
+        // pause flag: a global `ResumeSession` must not un-stop a torrent that
+        // hit its ratio/time limit, and seeding-policy stops are a distinct
+        // concept from chunk0-1's session pause.
+        {
+            let ctxs = self.ctx.torrent_ctxs.read().await;
+            if let Some(ctx) = ctxs.get(&state.info_hash) {
+                let _ = ctx.tx.send(TorrentMsg::Stop).await;
+            }
+        }
+        self.publish_alert(Alert::SeedingStopped { info_hash: state.info_hash, reason }).await;
+    }
+
+    /// Whether every torrent has reached the end of its seeding life: a
+    /// torrent with a ratio/time policy must have been stopped by it, and a
+    /// torrent without one must simply be `Seeding`. Used to gate
+    /// `quit_after_complete` — a torrent stopped by its policy is no longer
+    /// `Seeding`, so the two states are checked together here.
+    async fn all_seeding_policy_met(&self) -> bool {
+        let states = self.ctx.torrent_states.read().await;
+        let configs = self.ctx.torrent_configs.read().await;
+        let seeding = self.ctx.seeding_since.read().await;
+
+        states.values().all(|state| {
+            let config = configs.get(&state.info_hash);
+            let has_policy = config.and_then(|c| c.seed_ratio_limit).or(self.config.seed_ratio_limit).is_some()
+                || config.and_then(|c| c.seed_time_limit).or(self.config.seed_time_limit).is_some();
+            if has_policy {
+                seeding.get(&state.info_hash).is_some_and(|s| s.stopped)
+            } else {
+                state.status == TorrentStatus::Seeding
+            }
+        })
+    }
+
     async fn quit(&mut self) -> Result<(), Error> {
+        // checkpoint one last time so progress survives the restart.
+        self.save_session().await;
+
         // tell all torrents that we are gracefully shutting down,
         // each torrent will kill their peers tasks, and their tracker task
         let ctxs = self.ctx.torrent_ctxs.read().await.clone();
@@ -0,0 +1,285 @@
+//! Bandwidth limiting and smoothed throughput accounting.
+//!
+//! Two cooperating pieces:
+//!
+//! - [`RateLimiter`], a token bucket refilled at a fixed byte-rate. Peer read
+//!   and write loops, and the HTTP [`stream`](crate::stream) server, call
+//!   [`RateLimiter::acquire`] before moving bytes and are told how many they
+//!   may transfer; an empty bucket yields until the next refill. A global
+//!   bucket lives on [`DaemonCtx`](crate::daemon::DaemonCtx) and an optional
+//!   [`TorrentLimiters`] pair narrows it further for a single torrent, composed
+//!   through [`DaemonCtx::acquire_download`](crate::daemon::DaemonCtx::acquire_download)
+//!   and [`DaemonCtx::acquire_upload`](crate::daemon::DaemonCtx::acquire_upload).
+//! - [`ThruputCounter`], a rolling counter that reports both the instantaneous
+//!   rate and a 5-second EWMA, and [`TorrentThruput`], which folds a torrent's
+//!   raw `downloaded` total into one so
+//!   [`TorrentState::download_rate`](crate::torrent::TorrentState) shows a
+//!   steady number instead of a jittery per-tick delta.
+use std::time::{Duration, Instant};
+
+/// A token bucket metering bytes per second. A rate of `None` means unlimited,
+/// in which case [`acquire`](Self::acquire) always grants the full request.
+#[derive(Debug)]
+pub struct RateLimiter {
+    /// Bytes per second, or `None` for unlimited.
+    rate: Option<u64>,
+    /// Currently available byte permits.
+    tokens: f64,
+    /// Last time the bucket was refilled.
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    /// A bucket limited to `rate` bytes/s, or unlimited when `rate` is `None`.
+    pub fn new(rate: Option<u64>) -> Self {
+        Self { rate, tokens: rate.unwrap_or(0) as f64, last_refill: Instant::now() }
+    }
+
+    /// Change the limit at runtime; the bucket keeps whatever it had banked.
+    pub fn set_rate(&mut self, rate: Option<u64>) {
+        self.rate = rate;
+    }
+
+    /// Top up the bucket for the time elapsed since the last refill, clamped
+    /// to one second's worth of permits so an idle bucket cannot burst.
+    fn refill(&mut self, now: Instant) {
+        let Some(rate) = self.rate else { return };
+        let elapsed = now.saturating_duration_since(self.last_refill).as_secs_f64();
+        self.last_refill = now;
+        self.tokens = (self.tokens + elapsed * rate as f64).min(rate as f64);
+    }
+
+    /// Grant up to `wanted` byte permits, never more than the bucket holds.
+    /// Returns the number granted; `0` means the caller should wait for the
+    /// next refill. An unlimited bucket always grants `wanted`.
+    pub fn acquire(&mut self, wanted: u64) -> u64 {
+        if self.rate.is_none() {
+            return wanted;
+        }
+        self.refill(Instant::now());
+        let granted = (self.tokens.floor() as u64).min(wanted);
+        self.tokens -= granted as f64;
+        granted
+    }
+
+    /// Hand back `amount` permits a caller acquired but ended up not using,
+    /// clamped to the bucket's own capacity. Used when composing two buckets
+    /// (see [`DaemonCtx::acquire`](crate::daemon::DaemonCtx::acquire)): the
+    /// first bucket is debited for a provisional grant before the second is
+    /// known, and any part the second bucket could not match is refunded here
+    /// instead of being lost. A no-op on an unlimited bucket, matching how
+    /// [`Self::acquire`] never debits one either.
+    pub fn refund(&mut self, amount: u64) {
+        let Some(rate) = self.rate else { return };
+        self.tokens = (self.tokens + amount as f64).min(rate as f64);
+    }
+}
+
+/// A torrent's own download/upload buckets, narrowing the global buckets on
+/// [`DaemonCtx`](crate::daemon::DaemonCtx) for that one torrent. Stored behind
+/// `info_hash` in [`DaemonCtx::torrent_limiters`](crate::daemon::DaemonCtx::torrent_limiters)
+/// and consulted through [`DaemonCtx::acquire_download`](crate::daemon::DaemonCtx::acquire_download)
+/// / [`DaemonCtx::acquire_upload`](crate::daemon::DaemonCtx::acquire_upload).
+#[derive(Debug)]
+pub struct TorrentLimiters {
+    pub download: RateLimiter,
+    pub upload: RateLimiter,
+}
+
+impl TorrentLimiters {
+    /// A pair of buckets for one torrent, seeded from its
+    /// [`TorrentConfig`](crate::daemon::TorrentConfig) rate caps.
+    pub fn new(max_download_rate: Option<u64>, max_upload_rate: Option<u64>) -> Self {
+        Self {
+            download: RateLimiter::new(max_download_rate),
+            upload: RateLimiter::new(max_upload_rate),
+        }
+    }
+}
+
+/// Rolling up/down byte counter with an exponentially-weighted moving average.
+///
+/// Bytes are accumulated with [`add`](Self::add) and folded into the averages
+/// once per [`tick`](Self::tick). The EWMA is tuned so a burst decays over
+/// roughly a 5-second window, matching the `TorrentState` refresh the UI reads.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ThruputCounter {
+    /// Bytes accumulated since the last tick.
+    round: u64,
+    /// Bytes/s over the most recent tick.
+    instant: u64,
+    /// 5s exponentially-weighted average, in bytes/s.
+    avg: f64,
+    /// Highest instantaneous rate seen so far, in bytes/s.
+    peak: u64,
+}
+
+impl ThruputCounter {
+    /// Smoothing factor for a 5-sample EWMA: `2 / (N + 1)` with `N = 5`.
+    const ALPHA: f64 = 2.0 / 6.0;
+
+    /// Account for `bytes` transferred since the last tick.
+    pub fn add(&mut self, bytes: u64) {
+        self.round = self.round.saturating_add(bytes);
+    }
+
+    /// Fold the bytes accumulated over `elapsed` into the instantaneous rate,
+    /// the EWMA and the peak, then start a fresh round.
+    pub fn tick(&mut self, elapsed: Duration) {
+        let secs = elapsed.as_secs_f64().max(f64::EPSILON);
+        self.instant = (self.round as f64 / secs) as u64;
+        self.avg = Self::ALPHA * self.instant as f64 + (1.0 - Self::ALPHA) * self.avg;
+        self.peak = self.peak.max(self.instant);
+        self.round = 0;
+    }
+
+    /// The most recent instantaneous rate, in bytes/s.
+    pub fn instant(&self) -> u64 {
+        self.instant
+    }
+
+    /// The smoothed 5s-average rate, in bytes/s.
+    pub fn avg(&self) -> u64 {
+        self.avg as u64
+    }
+
+    /// The highest instantaneous rate observed, in bytes/s.
+    pub fn peak(&self) -> u64 {
+        self.peak
+    }
+}
+
+/// Per-torrent throughput bookkeeping, fed by the raw `downloaded` total in
+/// each [`TorrentState`](crate::torrent::TorrentState) tick and folded into a
+/// [`ThruputCounter`] so the daemon can report a smoothed rate instead of the
+/// jittery per-tick delta.
+///
+/// Only the download direction is tracked: `TorrentState` has nowhere to
+/// surface an upload rate, and `uploaded` is already used as a raw total for
+/// ratio accounting in [`Daemon::enforce_seed_policy`](crate::daemon::Daemon::enforce_seed_policy),
+/// so a second counter here would just compute values nothing reads.
+#[derive(Debug)]
+pub struct TorrentThruput {
+    download: ThruputCounter,
+    last_downloaded: u64,
+    last_tick: Instant,
+}
+
+impl TorrentThruput {
+    /// Start tracking a torrent from its current total, so the first
+    /// [`Self::observe`] call measures only what changes from here on.
+    pub fn new(downloaded: u64) -> Self {
+        Self { download: ThruputCounter::default(), last_downloaded: downloaded, last_tick: Instant::now() }
+    }
+
+    /// Fold the bytes downloaded since the last observation into the counter
+    /// and return the smoothed rate, in bytes/s.
+    pub fn observe(&mut self, downloaded: u64) -> u64 {
+        let now = Instant::now();
+        let elapsed = now.saturating_duration_since(self.last_tick);
+
+        self.download.add(downloaded.saturating_sub(self.last_downloaded));
+        self.download.tick(elapsed);
+
+        self.last_downloaded = downloaded;
+        self.last_tick = now;
+
+        self.download.avg()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unlimited_bucket_always_grants_the_full_request() {
+        let mut limiter = RateLimiter::new(None);
+        assert_eq!(limiter.acquire(4096), 4096);
+        assert_eq!(limiter.acquire(u64::MAX), u64::MAX);
+    }
+
+    #[test]
+    fn limited_bucket_drains_and_caps_at_wanted() {
+        // a fresh bucket starts full, so the first grants come straight out of
+        // the banked permits without waiting for a refill.
+        let mut limiter = RateLimiter::new(Some(1000));
+        assert_eq!(limiter.acquire(600), 600);
+        // only 400 permits remain, even though 600 were asked for.
+        assert_eq!(limiter.acquire(600), 400);
+        // drained: the caller is told to wait.
+        assert_eq!(limiter.acquire(600), 0);
+    }
+
+    #[test]
+    fn tick_reports_instant_rate_and_tracks_peak() {
+        let mut counter = ThruputCounter::default();
+        counter.add(1000);
+        counter.tick(Duration::from_secs(1));
+        assert_eq!(counter.instant(), 1000);
+        assert_eq!(counter.peak(), 1000);
+
+        // a quieter round lowers the instant rate but not the peak.
+        counter.add(200);
+        counter.tick(Duration::from_secs(1));
+        assert_eq!(counter.instant(), 200);
+        assert_eq!(counter.peak(), 1000);
+    }
+
+    #[test]
+    fn refund_hands_back_unused_permits_clamped_to_capacity() {
+        let mut limiter = RateLimiter::new(Some(1000));
+        assert_eq!(limiter.acquire(1000), 1000);
+        limiter.refund(400);
+        assert_eq!(limiter.acquire(1000), 400);
+
+        // refunding more than the bucket can hold clamps to its capacity
+        // instead of letting it bank an unbounded burst.
+        limiter.refund(10_000);
+        assert_eq!(limiter.acquire(10_000), 1000);
+    }
+
+    #[test]
+    fn refund_is_a_no_op_on_an_unlimited_bucket() {
+        let mut limiter = RateLimiter::new(None);
+        limiter.refund(500);
+        assert_eq!(limiter.acquire(u64::MAX), u64::MAX);
+    }
+
+    #[test]
+    fn torrent_limiters_cap_each_direction_independently() {
+        let mut limiters = TorrentLimiters::new(Some(1000), None);
+        assert_eq!(limiters.download.acquire(1500), 1000);
+        // the upload side has no cap of its own.
+        assert_eq!(limiters.upload.acquire(u64::MAX), u64::MAX);
+    }
+
+    #[test]
+    fn torrent_thruput_smooths_the_delta_between_observations() {
+        let mut thruput = TorrentThruput::new(0);
+        // observe() measures elapsed wall-clock time, so we just assert the
+        // shape of the result here rather than an exact rate.
+        let down = thruput.observe(1000);
+        assert!(down > 0);
+
+        // no further bytes downloaded: the smoothed rate decays towards zero
+        // instead of holding at the previous value.
+        let still = thruput.observe(1000);
+        assert!(still < down);
+    }
+
+    #[test]
+    fn avg_smooths_towards_the_instant_rate() {
+        let mut counter = ThruputCounter::default();
+        // from a standing start the EWMA moves a fraction of the way up.
+        counter.add(1000);
+        counter.tick(Duration::from_secs(1));
+        let first = counter.avg();
+        assert!(first > 0 && first < 1000);
+
+        // sustaining the same rate pulls the average further towards it.
+        counter.add(1000);
+        counter.tick(Duration::from_secs(1));
+        assert!(counter.avg() > first);
+    }
+}
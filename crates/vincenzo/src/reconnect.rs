@@ -0,0 +1,216 @@
+//! Automatic peer reconnection with exponential backoff.
+//!
+//! Between tracker announces a torrent slowly bleeds peers as connections
+//! drop. The [`Daemon`](crate::daemon::Daemon) keeps one
+//! [`ReconnectSupervisor`] per torrent, fed by the [`Alert`](crate::alert::Alert)
+//! stream: a `PeerDisconnected` puts the address into
+//! [`PeerState::Backoff`], and on a periodic tick the daemon asks the
+//! supervisor which addresses are due for another handshake attempt.
+//!
+//! Backoff starts at [`ReconnectSupervisor::BASE`] and doubles on every failed
+//! attempt up to [`ReconnectSupervisor::CAP`]; a successful handshake resets
+//! the counter. No more than [`ReconnectSupervisor::MAX_IN_FLIGHT`] attempts
+//! run at once so a dead swarm does not spawn an unbounded number of dials.
+use std::{collections::HashMap, net::SocketAddr, time::{Duration, Instant}};
+
+/// The connection state the supervisor tracks for a single peer address.
+#[derive(Debug, Clone)]
+pub enum PeerState {
+    /// The peer is currently connected.
+    Connected,
+    /// The peer dropped and a reconnect attempt is in flight.
+    Connecting {
+        /// When the attempt was dialed, used to time it out if no
+        /// handshake/disconnect alert ever comes back.
+        since: Instant,
+        /// Consecutive failed attempts leading up to this dial.
+        attempts: u32,
+    },
+    /// The peer dropped and is waiting out its backoff window.
+    Backoff {
+        /// When the next attempt becomes due.
+        until: Instant,
+        /// Number of consecutive failed attempts so far.
+        attempts: u32,
+    },
+}
+
+/// Per-torrent reconnection bookkeeping. Not `Send`-shared; the daemon owns it
+/// behind its own lock and mutates it from the event loop.
+#[derive(Debug, Default)]
+pub struct ReconnectSupervisor {
+    peers: HashMap<SocketAddr, PeerState>,
+    in_flight: u32,
+}
+
+impl ReconnectSupervisor {
+    /// First backoff window after a peer drops.
+    pub const BASE: Duration = Duration::from_secs(2);
+    /// Upper bound the doubling backoff is clamped to.
+    pub const CAP: Duration = Duration::from_secs(300);
+    /// Maximum number of reconnect attempts in flight at once.
+    pub const MAX_IN_FLIGHT: u32 = 8;
+    /// How long a [`PeerState::Connecting`] attempt may linger before it is
+    /// assumed to have silently failed and is put back into backoff. Without
+    /// this a dial that never produces a handshake or disconnect alert would
+    /// pin its in-flight slot forever.
+    pub const CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+
+    /// Record that a handshake with `peer` succeeded, clearing any backoff.
+    pub fn on_connected(&mut self, peer: SocketAddr) {
+        if matches!(self.peers.get(&peer), Some(PeerState::Connecting { .. })) {
+            self.in_flight = self.in_flight.saturating_sub(1);
+        }
+        self.peers.insert(peer, PeerState::Connected);
+    }
+
+    /// Record that `peer` dropped, scheduling its next reconnect attempt. A
+    /// peer that was already connecting or backing off counts one more
+    /// failure and waits a doubled window.
+    pub fn on_disconnected(&mut self, peer: SocketAddr, now: Instant) {
+        let attempts = match self.peers.get(&peer) {
+            // a drop mid-attempt counts as a failed attempt.
+            Some(PeerState::Connecting { attempts, .. }) => {
+                self.in_flight = self.in_flight.saturating_sub(1);
+                attempts.saturating_add(1)
+            }
+            Some(PeerState::Backoff { attempts, .. }) => attempts.saturating_add(1),
+            _ => 1,
+        };
+        self.peers.insert(
+            peer,
+            PeerState::Backoff { until: now + Self::backoff(attempts), attempts },
+        );
+    }
+
+    /// Addresses whose backoff has elapsed and that should be retried now,
+    /// capped by [`Self::MAX_IN_FLIGHT`]. The returned peers move to
+    /// [`PeerState::Connecting`] and count against the in-flight budget.
+    ///
+    /// Attempts that have been `Connecting` longer than
+    /// [`Self::CONNECT_TIMEOUT`] are first swept back into backoff so a dial
+    /// that died without any alert releases its slot.
+    pub fn due(&mut self, now: Instant) -> Vec<SocketAddr> {
+        // reclaim slots held by timed-out attempts before handing out new ones.
+        for state in self.peers.values_mut() {
+            if let PeerState::Connecting { since, attempts } = state {
+                if now.saturating_duration_since(*since) >= Self::CONNECT_TIMEOUT {
+                    let attempts = attempts.saturating_add(1);
+                    *state = PeerState::Backoff { until: now + Self::backoff(attempts), attempts };
+                    self.in_flight = self.in_flight.saturating_sub(1);
+                }
+            }
+        }
+
+        let mut due = Vec::new();
+        for (peer, state) in self.peers.iter_mut() {
+            if self.in_flight >= Self::MAX_IN_FLIGHT {
+                break;
+            }
+            if let PeerState::Backoff { until, attempts } = state {
+                if *until <= now {
+                    *state = PeerState::Connecting { since: now, attempts: *attempts };
+                    self.in_flight += 1;
+                    due.push(*peer);
+                }
+            }
+        }
+        due
+    }
+
+    /// A read-only view of every tracked peer's state, surfaced in
+    /// [`TorrentState`](crate::torrent::TorrentState) for the UI.
+    pub fn states(&self) -> &HashMap<SocketAddr, PeerState> {
+        &self.peers
+    }
+
+    /// The number of peers currently `(Connecting, Backoff)`, derived from
+    /// [`Self::states`] for display in `--stats` and the Tui.
+    pub fn counts(&self) -> (usize, usize) {
+        self.states().values().fold((0, 0), |(connecting, backoff), state| match state {
+            PeerState::Connecting { .. } => (connecting + 1, backoff),
+            PeerState::Backoff { .. } => (connecting, backoff + 1),
+            PeerState::Connected => (connecting, backoff),
+        })
+    }
+
+    /// The backoff window for the `attempts`-th consecutive failure: `BASE`
+    /// doubled `attempts - 1` times, clamped to `CAP`.
+    fn backoff(attempts: u32) -> Duration {
+        let shift = attempts.saturating_sub(1).min(u32::BITS - 1);
+        Self::BASE.saturating_mul(1u32 << shift).min(Self::CAP)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn addr(n: u16) -> SocketAddr {
+        ([127, 0, 0, 1], n).into()
+    }
+
+    #[test]
+    fn backoff_doubles_and_clamps() {
+        assert_eq!(ReconnectSupervisor::backoff(1), Duration::from_secs(2));
+        assert_eq!(ReconnectSupervisor::backoff(2), Duration::from_secs(4));
+        assert_eq!(ReconnectSupervisor::backoff(3), Duration::from_secs(8));
+        // large attempt counts saturate at the cap, never overflow.
+        assert_eq!(ReconnectSupervisor::backoff(64), ReconnectSupervisor::CAP);
+    }
+
+    #[test]
+    fn due_caps_in_flight_and_frees_on_connect() {
+        let now = Instant::now();
+        let mut sup = ReconnectSupervisor::default();
+        for i in 0..20 {
+            sup.on_disconnected(addr(i), now);
+        }
+
+        // first backoff window elapsed: only MAX_IN_FLIGHT are handed out.
+        let after = now + ReconnectSupervisor::BASE;
+        let due = sup.due(after);
+        assert_eq!(due.len() as u32, ReconnectSupervisor::MAX_IN_FLIGHT);
+
+        // the budget is exhausted until a slot frees.
+        assert!(sup.due(after).is_empty());
+
+        sup.on_connected(due[0]);
+        assert_eq!(sup.due(after).len(), 1);
+    }
+
+    #[test]
+    fn counts_splits_connecting_and_backoff_peers() {
+        let now = Instant::now();
+        let mut sup = ReconnectSupervisor::default();
+        sup.on_disconnected(addr(1), now);
+        // a second failure for addr(2) doubles its backoff window, so it is
+        // still waiting when addr(1)'s shorter window has already elapsed.
+        sup.on_disconnected(addr(2), now);
+        sup.on_disconnected(addr(2), now);
+
+        let after = now + ReconnectSupervisor::BASE;
+        sup.due(after);
+
+        assert_eq!(sup.counts(), (1, 1));
+    }
+
+    #[test]
+    fn due_reclaims_timed_out_connecting_slots() {
+        let now = Instant::now();
+        let mut sup = ReconnectSupervisor::default();
+        sup.on_disconnected(addr(1), now);
+
+        let t1 = now + ReconnectSupervisor::BASE;
+        assert_eq!(sup.due(t1), vec![addr(1)]);
+        // still Connecting, no alert came back: nothing new is due yet.
+        assert!(sup.due(t1).is_empty());
+
+        // past the connect timeout it is swept back to backoff and, once its
+        // (now longer) window elapses, handed out again.
+        let t2 = t1 + ReconnectSupervisor::CONNECT_TIMEOUT;
+        assert!(sup.due(t2).is_empty());
+        let t3 = t2 + ReconnectSupervisor::backoff(2);
+        assert_eq!(sup.due(t3), vec![addr(1)]);
+    }
+}
@@ -0,0 +1,157 @@
+//! Fast-resume across daemon restarts.
+//!
+//! Each torrent's progress is checkpointed to a file under `resume_dir`, keyed
+//! by info_hash, so a restart can re-add the torrent with its last-known peers
+//! and skip re-hashing pieces that were already verified. Records are
+//! serialized with bincode, mirroring how the rest of the daemon keeps its
+//! on-wire data compact.
+use std::{
+    net::SocketAddr, path::{Path, PathBuf}
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+
+/// Extension used for the per-torrent resume files.
+const RESUME_EXT: &str = "resume";
+
+/// A checkpoint of everything needed to resume a torrent without
+/// re-downloading or re-hashing what is already on disk.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResumeRecord {
+    /// The magnet link the torrent was added with.
+    pub magnet: String,
+    /// The torrent's info_hash, also the file key.
+    pub info_hash: [u8; 20],
+    /// The directory the torrent was downloading into.
+    pub download_dir: String,
+    /// Bitfield of verified pieces; a set bit skips re-verification on resume.
+    pub pieces: Vec<u8>,
+    /// Cumulative downloaded bytes.
+    pub downloaded: u64,
+    /// Cumulative uploaded bytes.
+    pub uploaded: u64,
+    /// The peers the torrent was last connected to, retried on resume.
+    pub peers: Vec<SocketAddr>,
+}
+
+impl ResumeRecord {
+    /// The path of this record inside `resume_dir`.
+    pub fn path(&self, resume_dir: impl AsRef<Path>) -> PathBuf {
+        resume_dir.as_ref().join(hex(&self.info_hash)).with_extension(RESUME_EXT)
+    }
+
+    /// Serialize and atomically persist this record to `resume_dir`.
+    ///
+    /// The bytes are written to a sibling `.tmp` file first and then renamed
+    /// over the final path, so a crash mid-write leaves the previous record
+    /// intact instead of a truncated file that [`load_all`] would discard.
+    pub fn save(&self, resume_dir: impl AsRef<Path>) -> Result<(), Error> {
+        std::fs::create_dir_all(resume_dir.as_ref())?;
+        let bytes = bincode::serialize(self)?;
+
+        let path = self.path(resume_dir);
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, bytes)?;
+        std::fs::rename(&tmp, &path)?;
+        Ok(())
+    }
+}
+
+/// Load every resume record found in `resume_dir`. Files that fail to parse
+/// are skipped rather than aborting the whole restore.
+pub fn load_all(resume_dir: impl AsRef<Path>) -> Result<Vec<ResumeRecord>, Error> {
+    let dir = resume_dir.as_ref();
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut records = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some(RESUME_EXT) {
+            continue;
+        }
+        match std::fs::read(&path).map_err(Error::from).and_then(|bytes| {
+            bincode::deserialize(&bytes).map_err(Error::from)
+        }) {
+            Ok(record) => records.push(record),
+            Err(e) => tracing::warn!("skipping bad resume file {path:?}: {e:#?}"),
+        }
+    }
+
+    Ok(records)
+}
+
+/// Lowercase hex encoding of an info_hash, used as the file name.
+fn hex(bytes: &[u8; 20]) -> String {
+    let mut s = String::with_capacity(40);
+    for b in bytes {
+        s.push_str(&format!("{b:02x}"));
+    }
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch_dir(tag: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("vcz-resume-test-{tag}-{}", std::process::id()))
+    }
+
+    fn sample(info_hash: [u8; 20]) -> ResumeRecord {
+        ResumeRecord {
+            magnet: "magnet:?xt=urn:btih:deadbeef".into(),
+            info_hash,
+            download_dir: "/tmp/dl".into(),
+            pieces: vec![0b1010_1010, 0xff],
+            downloaded: 4096,
+            uploaded: 2048,
+            peers: vec![([127, 0, 0, 1], 51413).into()],
+        }
+    }
+
+    #[test]
+    fn save_then_load_all_round_trips() {
+        let dir = scratch_dir("roundtrip");
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let record = sample([1u8; 20]);
+        record.save(&dir).unwrap();
+
+        let loaded = load_all(&dir).unwrap();
+        assert_eq!(loaded.len(), 1);
+        let got = &loaded[0];
+        assert_eq!(got.info_hash, record.info_hash);
+        assert_eq!(got.pieces, record.pieces);
+        assert_eq!(got.downloaded, record.downloaded);
+        assert_eq!(got.uploaded, record.uploaded);
+        assert_eq!(got.peers, record.peers);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn load_all_skips_junk_and_missing_dir() {
+        // a directory that was never created yields an empty set, not an error.
+        let missing = scratch_dir("missing");
+        let _ = std::fs::remove_dir_all(&missing);
+        assert!(load_all(&missing).unwrap().is_empty());
+
+        // a non-resume file and a corrupt resume file are both ignored.
+        let dir = scratch_dir("junk");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("notes.txt"), b"ignore me").unwrap();
+        std::fs::write(dir.join("garbage").with_extension(RESUME_EXT), b"not bincode").unwrap();
+        sample([2u8; 20]).save(&dir).unwrap();
+
+        let loaded = load_all(&dir).unwrap();
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].info_hash, [2u8; 20]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,332 @@
+//! HTTP streaming of files inside a torrent.
+//!
+//! This spawns a tiny HTTP/1.1 server that lets a media player (VLC, mpv, ...)
+//! play a file before the torrent has finished downloading. A request to
+//! `http://host:port/<info_hash>/<file_id>` returns the file, and a `Range`
+//! request translates the requested byte offsets into piece indices,
+//! prioritizes those pieces for download, blocks until they arrive, and
+//! answers with `206 Partial Content`.
+//!
+//! Unlike the [`Daemon`](crate::daemon::Daemon) protocol, which is a binary
+//! [`DaemonCodec`](crate::daemon_wire::DaemonCodec) over TCP, this endpoint
+//! speaks HTTP because that is what media players understand.
+use std::{net::SocketAddr, sync::Arc, time::Duration};
+
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt}, net::{TcpListener, TcpStream}, spawn, sync::oneshot, time::sleep
+};
+use tracing::{error, info, trace, warn};
+
+use crate::{
+    daemon::DaemonCtx, error::Error, torrent::TorrentMsg
+};
+
+/// HTTP server that streams torrent files with byte-range support.
+pub struct HttpStream {
+    pub ctx: Arc<DaemonCtx>,
+}
+
+/// A parsed `Range` header resolved against the file size. Both bounds are
+/// inclusive, following the HTTP spec.
+#[derive(Debug, Clone, Copy)]
+struct ByteRange {
+    start: u64,
+    end: u64,
+}
+
+impl HttpStream {
+    /// Size of each window streamed from the torrent, bounding how much of the
+    /// body is held in memory at once.
+    const CHUNK_SIZE: u64 = 1024 * 1024;
+
+    /// How long to wait before retrying an empty upload bucket.
+    const RATE_LIMIT_POLL: Duration = Duration::from_millis(50);
+
+    pub fn new(ctx: Arc<DaemonCtx>) -> Self {
+        Self { ctx }
+    }
+
+    /// Bind the HTTP server and serve requests until the listener is dropped.
+    pub async fn run(self, addr: SocketAddr) -> Result<(), Error> {
+        let listener = TcpListener::bind(addr).await?;
+        info!("streaming server listening on: http://{addr}");
+
+        loop {
+            let (socket, peer) = listener.accept().await?;
+            trace!("stream connection from {peer}");
+            let ctx = self.ctx.clone();
+
+            spawn(async move {
+                if let Err(e) = Self::handle(socket, ctx).await {
+                    warn!("stream request failed: {e:#?}");
+                }
+            });
+        }
+    }
+
+    /// Handle a single HTTP request on `socket`.
+    async fn handle(
+        mut socket: TcpStream,
+        ctx: Arc<DaemonCtx>,
+    ) -> Result<(), Error> {
+        let head = Self::read_head(&mut socket).await?;
+
+        let (info_hash, file_id) = match Self::parse_target(&head) {
+            Some(target) => target,
+            None => {
+                return Self::respond_error(&mut socket, 400, "Bad Request")
+                    .await;
+            }
+        };
+
+        // the total size of the requested file, needed to clamp the range and
+        // fill in Content-Range.
+        let file_size = match Self::file_size(&ctx, info_hash, file_id).await {
+            Some(size) => size,
+            None => {
+                return Self::respond_error(&mut socket, 404, "Not Found").await;
+            }
+        };
+
+        let range = Self::parse_range(&head, file_size);
+
+        let (start, end) = match range {
+            Some(ByteRange { start, end }) => (start, end),
+            // no Range header: stream the whole file sequentially.
+            None => (0, file_size.saturating_sub(1)),
+        };
+
+        // an empty file, or a range that starts past the end, is unsatisfiable.
+        if file_size == 0 || start > end || start >= file_size {
+            return Self::respond_error(
+                &mut socket,
+                416,
+                "Range Not Satisfiable",
+            )
+            .await;
+        }
+
+        // the satisfiable range's length is known up front, so send a
+        // determinate Content-Length: many players (VLC, mpv) need it on a
+        // 206 to seek within the stream at all.
+        let content_length = end - start + 1;
+        let status =
+            if range.is_some() { "206 Partial Content" } else { "200 OK" };
+        let mut header = format!(
+            "HTTP/1.1 {status}\r\n\
+             Accept-Ranges: bytes\r\n\
+             Content-Type: application/octet-stream\r\n\
+             Content-Length: {content_length}\r\n"
+        );
+        if range.is_some() {
+            header.push_str(&format!(
+                "Content-Range: bytes {start}-{end}/{file_size}\r\n"
+            ));
+        }
+        header.push_str("\r\n");
+        socket.write_all(header.as_bytes()).await?;
+
+        // stream the range in bounded windows, asking the torrent for each one
+        // and writing it to the socket as soon as its covering pieces are
+        // verified. This lets a player start within the first window instead of
+        // waiting for the whole file, and keeps at most `CHUNK_SIZE` bytes of
+        // the body in memory at once.
+        let torrent_tx = {
+            let ctxs = ctx.torrent_ctxs.read().await;
+            ctxs.get(&info_hash).ok_or(Error::TorrentDoesNotExist)?.tx.clone()
+        };
+
+        let mut cursor = start;
+        while cursor <= end {
+            let chunk_end = (cursor + Self::CHUNK_SIZE - 1).min(end);
+
+            let (tx, rx) = oneshot::channel();
+            torrent_tx
+                .send(TorrentMsg::StreamBytes { file_id, start: cursor, end: chunk_end, recipient: tx })
+                .await?;
+            let bytes = rx.await?;
+
+            // a short read means the torrent could not supply the rest. The
+            // Content-Length we already sent has made a promise we can no
+            // longer keep, so drop the connection instead of writing a body
+            // shorter than advertised.
+            if bytes.is_empty() {
+                warn!("stream to {info_hash:?} truncated at byte {cursor}, dropping connection");
+                return Ok(());
+            }
+
+            Self::write_rate_limited(&mut socket, &ctx, &info_hash, &bytes).await?;
+            cursor += bytes.len() as u64;
+        }
+
+        socket.flush().await?;
+
+        Ok(())
+    }
+
+    /// Write `body` to `socket` in permit-sized slices, acquired from the
+    /// daemon's global/per-torrent upload buckets so a streamed file counts
+    /// against the same `--max-upload-rate` cap as peer traffic. An empty
+    /// bucket is retried after [`Self::RATE_LIMIT_POLL`] instead of bursting.
+    async fn write_rate_limited(
+        socket: &mut TcpStream,
+        ctx: &Arc<DaemonCtx>,
+        info_hash: &[u8; 20],
+        mut body: &[u8],
+    ) -> Result<(), Error> {
+        while !body.is_empty() {
+            let granted = ctx.acquire_upload(info_hash, body.len() as u64).await;
+            if granted == 0 {
+                sleep(Self::RATE_LIMIT_POLL).await;
+                continue;
+            }
+            let granted = granted as usize;
+            socket.write_all(&body[..granted]).await?;
+            body = &body[granted..];
+        }
+        Ok(())
+    }
+
+    /// Read from `socket` until the end of the HTTP request header
+    /// (`\r\n\r\n`), so a header split across TCP segments is read whole.
+    async fn read_head(socket: &mut TcpStream) -> Result<String, Error> {
+        let mut buf = Vec::with_capacity(1024);
+        let mut chunk = [0u8; 1024];
+
+        loop {
+            let n = socket.read(&mut chunk).await?;
+            if n == 0 {
+                break;
+            }
+            buf.extend_from_slice(&chunk[..n]);
+
+            if buf.windows(4).any(|w| w == b"\r\n\r\n") || buf.len() > 16 * 1024 {
+                break;
+            }
+        }
+
+        Ok(String::from_utf8_lossy(&buf).into_owned())
+    }
+
+    /// Parse the request target `/<info_hash>/<file_id>` out of the request
+    /// line, returning the decoded info_hash and file index.
+    fn parse_target(head: &str) -> Option<([u8; 20], usize)> {
+        let line = head.lines().next()?;
+        let path = line.split_whitespace().nth(1)?;
+        let mut segments = path.trim_matches('/').split('/');
+
+        let hash_hex = segments.next()?.as_bytes();
+        let file_id = segments.next()?.parse().ok()?;
+
+        if hash_hex.len() != 40 {
+            return None;
+        }
+        let mut info_hash = [0u8; 20];
+        for (i, byte) in info_hash.iter_mut().enumerate() {
+            let hi = (hash_hex[i * 2] as char).to_digit(16)?;
+            let lo = (hash_hex[i * 2 + 1] as char).to_digit(16)?;
+            *byte = (hi * 16 + lo) as u8;
+        }
+
+        Some((info_hash, file_id))
+    }
+
+    /// Parse a `Range` header into an inclusive `[start, end]` clamped against
+    /// `file_size`. Supports `bytes=start-end`, `bytes=start-` (open-ended),
+    /// and `bytes=-suffix` (the last `suffix` bytes).
+    fn parse_range(head: &str, file_size: u64) -> Option<ByteRange> {
+        let value = head
+            .lines()
+            .find_map(|l| {
+                l.strip_prefix("Range:").or_else(|| l.strip_prefix("range:"))
+            })?
+            .trim();
+        let spec = value.strip_prefix("bytes=")?;
+        let (start, end) = spec.split_once('-')?;
+        let last = file_size.saturating_sub(1);
+
+        let range = match (start.trim(), end.trim()) {
+            // suffix range: the last N bytes of the file.
+            ("", suffix) => {
+                let n: u64 = suffix.parse().ok()?;
+                ByteRange { start: file_size.saturating_sub(n), end: last }
+            }
+            (start, "") => {
+                ByteRange { start: start.parse().ok()?, end: last }
+            }
+            (start, end) => ByteRange {
+                start: start.parse().ok()?,
+                end: end.parse::<u64>().ok()?.min(last),
+            },
+        };
+
+        Some(range)
+    }
+
+    /// Look up the size of a file inside a torrent from the shared state.
+    async fn file_size(
+        ctx: &Arc<DaemonCtx>,
+        info_hash: [u8; 20],
+        file_id: usize,
+    ) -> Option<u64> {
+        let states = ctx.torrent_states.read().await;
+        states.get(&info_hash)?.files.get(file_id).map(|f| f.length)
+    }
+
+    /// Write a bare HTTP error response: the status line and an empty body
+    /// (`Content-Length: 0`).
+    async fn respond_error(
+        socket: &mut TcpStream,
+        code: u16,
+        reason: &str,
+    ) -> Result<(), Error> {
+        error!("stream responding {code} {reason}");
+        let response = format!(
+            "HTTP/1.1 {code} {reason}\r\nContent-Length: 0\r\n\r\n"
+        );
+        socket.write_all(response.as_bytes()).await?;
+        socket.flush().await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_target_decodes_hash_and_file_id() {
+        let head = "GET /aabbccddeeff00112233445566778899aabbccdd/3 HTTP/1.1\r\n\r\n";
+        let (info_hash, file_id) = HttpStream::parse_target(head).unwrap();
+        assert_eq!(info_hash[0], 0xaa);
+        assert_eq!(info_hash[19], 0xdd);
+        assert_eq!(file_id, 3);
+    }
+
+    #[test]
+    fn parse_target_rejects_malformed_paths() {
+        // too-short hash, missing file id, non-hex.
+        assert!(HttpStream::parse_target("GET /deadbeef/0 HTTP/1.1\r\n").is_none());
+        assert!(HttpStream::parse_target("GET /aabbccddeeff00112233445566778899aabbccdd HTTP/1.1\r\n").is_none());
+        assert!(HttpStream::parse_target("GET /zzbbccddeeff00112233445566778899aabbccdd/0 HTTP/1.1\r\n").is_none());
+    }
+
+    #[test]
+    fn parse_range_closed_open_and_suffix() {
+        let closed = HttpStream::parse_range("Range: bytes=10-99\r\n", 1000).unwrap();
+        assert_eq!((closed.start, closed.end), (10, 99));
+
+        let open = HttpStream::parse_range("Range: bytes=10-\r\n", 1000).unwrap();
+        assert_eq!((open.start, open.end), (10, 999));
+
+        let suffix = HttpStream::parse_range("Range: bytes=-100\r\n", 1000).unwrap();
+        assert_eq!((suffix.start, suffix.end), (900, 999));
+    }
+
+    #[test]
+    fn parse_range_clamps_end_to_file_and_ignores_missing_header() {
+        let clamped = HttpStream::parse_range("range: bytes=0-5000\r\n", 1000).unwrap();
+        assert_eq!(clamped.end, 999);
+        assert!(HttpStream::parse_range("GET / HTTP/1.1\r\n", 1000).is_none());
+    }
+}